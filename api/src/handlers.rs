@@ -3,11 +3,210 @@ use std::process::{Command, Stdio};
 
 use rocket::data::{Data, ToByteUnit};
 use rocket::fs::NamedFile;
+use rocket::http::Status;
+use rocket::response::Responder;
 use rocket::serde::{json::Json, Deserialize, Serialize};
 use rocket::tokio::fs;
+use rocket::tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use rocket::tokio::process::{Child, Command as AsyncCommand};
+use rocket::tokio::sync::mpsc::Sender;
+use rocket::{Request, Response};
+use thiserror::Error;
 
 use crate::utils::lib::{get_file_ext, get_file_path, CAIRO_DIR, CASM_ROOT, SIERRA_ROOT};
 
+/// Errors that can occur while dispatching an API command or shelling out to a toolchain.
+///
+/// `Responder` maps each variant to the HTTP status Rocket should answer the request with.
+#[derive(Debug, Error)]
+pub enum CompileError {
+    #[error("file path not found")]
+    FilePathNotFound,
+    #[error("file extension not supported")]
+    UnsupportedExtension,
+    #[error("toolchain process I/O failed: {0}")]
+    SpawnFailed(#[from] std::io::Error),
+    #[error("toolchain produced non-utf8 output: {0}")]
+    InvalidUtf8(#[from] std::string::FromUtf8Error),
+    #[error("compilation failed: {0}")]
+    CompilationFailed(String),
+    #[error("unknown cairo toolchain version: {0}")]
+    UnknownCairoVersion(String),
+    #[error("scarb build did not produce *.starknet_artifacts.json metadata, so a contract cannot be selected")]
+    ArtifactsMetadataMissing,
+    #[error("contract `{0}` not found in the build artifacts")]
+    ContractNotFound(String),
+    #[error("{0}")]
+    UnsupportedAnalysisTarget(String),
+}
+
+impl CompileError {
+    /// Stable, machine-readable category token for this error, independent of the HTTP
+    /// status code or the human-readable message.
+    fn category(&self) -> &'static str {
+        match self {
+            CompileError::FilePathNotFound => "FilePathNotFound",
+            CompileError::UnsupportedExtension => "UnsupportedExtension",
+            CompileError::SpawnFailed(_) => "SpawnFailed",
+            CompileError::InvalidUtf8(_) => "InvalidUtf8",
+            CompileError::CompilationFailed(_) => "CompilationFailed",
+            CompileError::UnknownCairoVersion(_) => "UnknownCairoVersion",
+            CompileError::ArtifactsMetadataMissing => "ArtifactsMetadataMissing",
+            CompileError::ContractNotFound(_) => "ContractNotFound",
+            CompileError::UnsupportedAnalysisTarget(_) => "UnsupportedAnalysisTarget",
+        }
+    }
+}
+
+impl<'r> Responder<'r, 'static> for CompileError {
+    fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let status = match self {
+            CompileError::FilePathNotFound
+            | CompileError::UnsupportedExtension
+            | CompileError::UnknownCairoVersion(_)
+            | CompileError::ContractNotFound(_)
+            | CompileError::UnsupportedAnalysisTarget(_) => Status::BadRequest,
+            CompileError::SpawnFailed(_) => Status::InternalServerError,
+            CompileError::InvalidUtf8(_) => Status::InternalServerError,
+            CompileError::CompilationFailed(_) => Status::UnprocessableEntity,
+            CompileError::ArtifactsMetadataMissing => Status::UnprocessableEntity,
+        };
+
+        let body = Json(CompileErrorBody {
+            status: self.category().to_string(),
+            message: self.to_string(),
+        })
+        .respond_to(request)?;
+
+        Response::build_from(body).status(status).ok()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+struct CompileErrorBody {
+    status: String,
+    message: String,
+}
+
+/// Version string used when a request does not ask for a specific Cairo toolchain
+const DEFAULT_CAIRO_VERSION: &str = "default";
+
+/// Name of the environment variable listing additional Cairo toolchain checkouts, as
+/// `version=/path/to/checkout` pairs separated by `;` (e.g.
+/// `CAIRO_TOOLCHAINS="2.0.0=/opt/cairo-2.0.0;2.1.0=/opt/cairo-2.1.0"`). Each path is built
+/// and dispatched to the same way `CAIRO_DIR` is today.
+const CAIRO_TOOLCHAINS_ENV: &str = "CAIRO_TOOLCHAINS";
+
+/// Registered Cairo toolchain checkouts, keyed by version string. `CAIRO_DIR` is always
+/// registered as `DEFAULT_CAIRO_VERSION` so existing callers keep working unmodified;
+/// any further versions are read from `CAIRO_TOOLCHAINS` so multiple toolchains can be
+/// installed side by side without a source change.
+fn cairo_version_registry() -> Vec<(String, String)> {
+    let mut registry = vec![(DEFAULT_CAIRO_VERSION.to_string(), CAIRO_DIR.to_string())];
+
+    if let Ok(toolchains) = std::env::var(CAIRO_TOOLCHAINS_ENV) {
+        for entry in toolchains.split(';') {
+            let entry = entry.trim();
+            if entry.is_empty() {
+                continue;
+            }
+            match entry.split_once('=') {
+                Some((version, dir)) => {
+                    registry.push((version.trim().to_string(), dir.trim().to_string()))
+                }
+                None => println!("LOG: ignoring malformed {CAIRO_TOOLCHAINS_ENV} entry: {entry}"),
+            }
+        }
+    }
+
+    registry
+}
+
+/// List the Cairo toolchain versions available for per-request selection
+pub fn do_list_cairo_versions() -> Vec<String> {
+    cairo_version_registry()
+        .into_iter()
+        .map(|(version, _)| version)
+        .collect()
+}
+
+/// Resolve the requested Cairo toolchain directory, falling back to `CAIRO_DIR` when no
+/// version is given
+fn resolve_cairo_dir(version: Option<&str>) -> Result<String, CompileError> {
+    match version {
+        None => Ok(CAIRO_DIR.to_string()),
+        Some(version) => cairo_version_registry()
+            .into_iter()
+            .find(|(name, _)| name == version)
+            .map(|(_, dir)| dir)
+            .ok_or_else(|| CompileError::UnknownCairoVersion(version.to_string())),
+    }
+}
+
+/// One line of output streamed live from a running toolchain process, already rewritten
+/// from the server-side path back to the path Remix uploaded the file under.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct CompileStreamLine {
+    pub stream: String,
+    pub line: String,
+}
+
+/// Read a child's stdout or stderr line-by-line, forwarding each rewritten line through
+/// `sender` as soon as it's produced (when given), while still collecting the full output
+/// so callers that want the old buffered `CompileResponse` shape keep working.
+async fn forward_lines(
+    reader: impl AsyncRead + Unpin,
+    stream_name: &'static str,
+    rewrites: &[(String, String)],
+    sender: Option<&Sender<CompileStreamLine>>,
+) -> Result<String, CompileError> {
+    let mut lines = BufReader::new(reader).lines();
+    let mut full = String::new();
+
+    while let Some(mut line) = lines.next_line().await? {
+        for (from, to) in rewrites {
+            line = line.replace(from.as_str(), to.as_str());
+        }
+
+        full.push_str(&line);
+        full.push('\n');
+
+        if let Some(sender) = sender {
+            let _ = sender
+                .send(CompileStreamLine {
+                    stream: stream_name.to_string(),
+                    line,
+                })
+                .await;
+        }
+    }
+
+    Ok(full)
+}
+
+/// Spawn a toolchain child process and stream its stdout/stderr back as they're produced
+/// instead of blocking until the whole compilation finishes. `sender` is `None` for the
+/// buffered fallback mode, in which case this simply collects the full output.
+async fn run_streaming(
+    mut child: Child,
+    rewrites: &[(String, String)],
+    sender: Option<&Sender<CompileStreamLine>>,
+) -> Result<(String, String, std::process::ExitStatus), CompileError> {
+    let stdout = child.stdout.take().expect("stdout was piped");
+    let stderr = child.stderr.take().expect("stderr was piped");
+
+    let (stdout_text, stderr_text) = rocket::tokio::join!(
+        forward_lines(stdout, "stdout", rewrites, sender),
+        forward_lines(stderr, "stderr", rewrites, sender)
+    );
+
+    let status = child.wait().await?;
+
+    Ok((stdout_text?, stderr_text?, status))
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(crate = "rocket::serde")]
 pub struct CompileResponse {
@@ -20,6 +219,9 @@ pub struct CompileResponse {
 pub struct FileContentMap {
     pub file_name: String,
     pub file_content: String,
+    pub artifact_kind: Option<String>,
+    pub package_name: Option<String>,
+    pub contract_name: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -29,12 +231,51 @@ pub struct ScarbCompileResponse {
     pub file_content_map_array: Vec<FileContentMap>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct TestResult {
+    pub name: String,
+    pub passed: bool,
+    pub gas_usage: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct TestRunResponse {
+    pub status: String,
+    pub message: String,
+    pub results: Vec<TestResult>,
+    pub passed_count: usize,
+    pub failed_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct Finding {
+    pub detector_id: String,
+    pub severity: String,
+    pub message: String,
+    pub function: String,
+    pub location: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct AnalyzeResponse {
+    pub status: String,
+    pub message: String,
+    pub findings: Vec<Finding>,
+}
+
 #[derive(Debug)]
 pub enum ApiCommand {
-    CairoVersion,
-    SierraCompile(PathBuf),
-    CasmCompile(PathBuf),
-    ScarbCompile(PathBuf),
+    CairoVersion(Option<String>),
+    SierraCompile(PathBuf, Option<String>),
+    CasmCompile(PathBuf, Option<String>),
+    ScarbCompile(PathBuf, Option<String>, Vec<String>),
+    RunTests(PathBuf),
+    Analyze(PathBuf, Vec<String>, Vec<String>),
+    ListVersions,
     #[allow(dead_code)]
     Shutdown,
 }
@@ -44,35 +285,65 @@ pub enum ApiCommandResult {
     CasmCompile(CompileResponse),
     SierraCompile(CompileResponse),
     ScarbCompile(ScarbCompileResponse),
+    RunTests(TestRunResponse),
+    Analyze(AnalyzeResponse),
+    ListVersions(Vec<String>),
     #[allow(dead_code)]
     Shutdown,
 }
 
-pub async fn dispatch_command(command: ApiCommand) -> Result<ApiCommandResult, String> {
+/// Run an `ApiCommand` to completion and collect its result.
+///
+/// `stream_sender` is `None` for the regular buffered request/response flow. A caller that
+/// wants live compiler output (e.g. a route that streams `CompileStreamLine`s back over
+/// SSE/chunked transfer as the child process runs) passes `Some(sender)`, which routes the
+/// sierra/CASM/Scarb commands through their `*_streamed` counterparts instead.
+pub async fn dispatch_command(
+    command: ApiCommand,
+    stream_sender: Option<Sender<CompileStreamLine>>,
+) -> Result<ApiCommandResult, CompileError> {
     match command {
-        ApiCommand::CairoVersion => match do_cairo_version() {
-            Ok(result) => Ok(ApiCommandResult::CairoVersion(result)),
-            Err(e) => Err(e),
-        },
-        ApiCommand::ScarbCompile(remix_file_path) => {
-            match do_scarb_compile(remix_file_path).await {
-                Ok(result) => Ok(ApiCommandResult::ScarbCompile(result.into_inner())),
-                Err(e) => Err(e),
-            }
+        ApiCommand::CairoVersion(version) => {
+            Ok(ApiCommandResult::CairoVersion(do_cairo_version(version)?))
         }
-        ApiCommand::SierraCompile(remix_file_path) => {
-            match do_compile_to_sierra(remix_file_path).await {
-                Ok(compile_response) => Ok(ApiCommandResult::SierraCompile(
-                    compile_response.into_inner(),
-                )),
-                Err(e) => Err(e),
-            }
+        ApiCommand::ScarbCompile(remix_file_path, contract, features) => {
+            let result = match stream_sender {
+                Some(sender) => {
+                    do_scarb_compile_streamed(remix_file_path, contract, features, sender).await?
+                }
+                None => do_scarb_compile(remix_file_path, contract, features).await?,
+            };
+            Ok(ApiCommandResult::ScarbCompile(result.into_inner()))
         }
-        ApiCommand::CasmCompile(remix_file_path) => {
-            match do_compile_to_casm(remix_file_path).await {
-                Json(compile_response) => Ok(ApiCommandResult::CasmCompile(compile_response)),
-            }
+        ApiCommand::SierraCompile(remix_file_path, version) => {
+            let compile_response = match stream_sender {
+                Some(sender) => {
+                    do_compile_to_sierra_streamed(remix_file_path, version, sender).await?
+                }
+                None => do_compile_to_sierra(remix_file_path, version).await?,
+            };
+            Ok(ApiCommandResult::SierraCompile(
+                compile_response.into_inner(),
+            ))
+        }
+        ApiCommand::CasmCompile(remix_file_path, version) => {
+            let compile_response = match stream_sender {
+                Some(sender) => {
+                    do_compile_to_casm_streamed(remix_file_path, version, sender).await?
+                }
+                None => do_compile_to_casm(remix_file_path, version).await?,
+            };
+            Ok(ApiCommandResult::CasmCompile(compile_response.into_inner()))
+        }
+        ApiCommand::RunTests(remix_file_path) => {
+            let result = do_run_tests(remix_file_path).await?;
+            Ok(ApiCommandResult::RunTests(result.into_inner()))
         }
+        ApiCommand::Analyze(remix_file_path, detectors, printers) => {
+            let result = do_analyze_with_options(remix_file_path, detectors, printers).await?;
+            Ok(ApiCommandResult::Analyze(result.into_inner()))
+        }
+        ApiCommand::ListVersions => Ok(ApiCommandResult::ListVersions(do_list_cairo_versions())),
         ApiCommand::Shutdown => Ok(ApiCommandResult::Shutdown),
     }
 }
@@ -127,17 +398,30 @@ pub async fn do_save_code(file: Data<'_>, remix_file_path: PathBuf) -> String {
 ///
 pub async fn do_compile_to_sierra(
     remix_file_path: PathBuf,
-) -> Result<Json<CompileResponse>, String> {
-    let remix_file_path = match remix_file_path.to_str() {
-        Some(path) => path.to_string(),
-        None => {
-            return Ok(Json(CompileResponse {
-                file_content: "".to_string(),
-                message: "File path not found".to_string(),
-                status: "FileNotFound".to_string(),
-            }));
-        }
-    };
+    version: Option<String>,
+) -> Result<Json<CompileResponse>, CompileError> {
+    do_compile_to_sierra_inner(remix_file_path, version, None).await
+}
+
+/// Same as [`do_compile_to_sierra`], but forwards each output line through `sender` as the
+/// compiler produces it instead of waiting for the process to exit.
+pub async fn do_compile_to_sierra_streamed(
+    remix_file_path: PathBuf,
+    version: Option<String>,
+    sender: Sender<CompileStreamLine>,
+) -> Result<Json<CompileResponse>, CompileError> {
+    do_compile_to_sierra_inner(remix_file_path, version, Some(&sender)).await
+}
+
+async fn do_compile_to_sierra_inner(
+    remix_file_path: PathBuf,
+    version: Option<String>,
+    sender: Option<&Sender<CompileStreamLine>>,
+) -> Result<Json<CompileResponse>, CompileError> {
+    let remix_file_path = remix_file_path
+        .to_str()
+        .ok_or(CompileError::FilePathNotFound)?
+        .to_string();
 
     // check if the file has .cairo extension
     match get_file_ext(&remix_file_path) {
@@ -146,20 +430,18 @@ pub async fn do_compile_to_sierra(
         }
         _ => {
             println!("LOG: File extension not supported");
-            return Ok(Json(CompileResponse {
-                file_content: "".to_string(),
-                message: "File extension not supported".to_string(),
-                status: "FileExtensionNotSupported".to_string(),
-            }));
+            return Err(CompileError::UnsupportedExtension);
         }
     }
 
+    let cairo_dir = resolve_cairo_dir(version.as_deref())?;
+
     let file_path = get_file_path(&remix_file_path);
 
     let sierra_remix_path = remix_file_path.replace(&get_file_ext(&remix_file_path), "sierra");
 
-    let mut compile = Command::new("cargo");
-    compile.current_dir(CAIRO_DIR);
+    let mut compile = AsyncCommand::new("cargo");
+    compile.current_dir(cairo_dir);
 
     // replace .cairo with
     let sierra_path = Path::new(SIERRA_ROOT).join(&sierra_remix_path);
@@ -179,7 +461,7 @@ pub async fn do_compile_to_sierra(
         }
     }
 
-    let result = compile
+    let child = compile
         .arg("run")
         .arg("--release")
         .arg("--bin")
@@ -190,12 +472,31 @@ pub async fn do_compile_to_sierra(
         .arg("--single-file")
         .stderr(Stdio::piped())
         .stdout(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute starknet-compile");
+        .spawn()?;
 
     println!("LOG: ran command:{:?}", compile);
 
-    let output = result.wait_with_output().expect("Failed to wait on child");
+    let rewrites = [
+        (
+            file_path
+                .to_str()
+                .ok_or(CompileError::FilePathNotFound)?
+                .to_string(),
+            remix_file_path.clone(),
+        ),
+        (
+            sierra_path
+                .to_str()
+                .ok_or(CompileError::FilePathNotFound)?
+                .to_string(),
+            sierra_remix_path.clone(),
+        ),
+    ];
+    let (_stdout, message, status) = run_streaming(child, &rewrites, sender).await?;
+
+    if !matches!(status.code(), Some(0)) {
+        return Err(CompileError::CompilationFailed(message));
+    }
 
     Ok(Json(CompileResponse {
         file_content: match NamedFile::open(&sierra_path).await.ok() {
@@ -208,34 +509,39 @@ pub async fn do_compile_to_sierra(
             },
             None => "".to_string(),
         },
-        message: String::from_utf8(output.stderr)
-            .unwrap()
-            .replace(&file_path.to_str().unwrap().to_string(), &remix_file_path)
-            .replace(
-                &sierra_path.to_str().unwrap().to_string(),
-                &sierra_remix_path,
-            ),
-        status: match output.status.code() {
-            Some(0) => "Success".to_string(),
-            Some(_) => "CompilationFailed".to_string(),
-            None => "UnknownError".to_string(),
-        },
+        message,
+        status: "Success".to_string(),
     }))
 }
 
 /// Compile source file to CASM
 ///
-pub async fn do_compile_to_casm(remix_file_path: PathBuf) -> Json<CompileResponse> {
-    let remix_file_path = match remix_file_path.to_str() {
-        Some(path) => path.to_string(),
-        None => {
-            return Json(CompileResponse {
-                file_content: "".to_string(),
-                message: "File path not found".to_string(),
-                status: "FileNotFound".to_string(),
-            });
-        }
-    };
+pub async fn do_compile_to_casm(
+    remix_file_path: PathBuf,
+    version: Option<String>,
+) -> Result<Json<CompileResponse>, CompileError> {
+    do_compile_to_casm_inner(remix_file_path, version, None).await
+}
+
+/// Same as [`do_compile_to_casm`], but forwards each output line through `sender` as the
+/// compiler produces it instead of waiting for the process to exit.
+pub async fn do_compile_to_casm_streamed(
+    remix_file_path: PathBuf,
+    version: Option<String>,
+    sender: Sender<CompileStreamLine>,
+) -> Result<Json<CompileResponse>, CompileError> {
+    do_compile_to_casm_inner(remix_file_path, version, Some(&sender)).await
+}
+
+async fn do_compile_to_casm_inner(
+    remix_file_path: PathBuf,
+    version: Option<String>,
+    sender: Option<&Sender<CompileStreamLine>>,
+) -> Result<Json<CompileResponse>, CompileError> {
+    let remix_file_path = remix_file_path
+        .to_str()
+        .ok_or(CompileError::FilePathNotFound)?
+        .to_string();
 
     // check if the file has .sierra extension
     match get_file_ext(&remix_file_path) {
@@ -244,20 +550,18 @@ pub async fn do_compile_to_casm(remix_file_path: PathBuf) -> Json<CompileRespons
         }
         _ => {
             println!("LOG: File extension not supported");
-            return Json(CompileResponse {
-                file_content: "".to_string(),
-                message: "File extension not supported".to_string(),
-                status: "FileExtensionNotSupported".to_string(),
-            });
+            return Err(CompileError::UnsupportedExtension);
         }
     }
 
+    let cairo_dir = resolve_cairo_dir(version.as_deref())?;
+
     let file_path = get_file_path(&remix_file_path);
 
     let casm_remix_path = remix_file_path.replace(&get_file_ext(&remix_file_path), "casm");
 
-    let mut compile = Command::new("cargo");
-    compile.current_dir(CAIRO_DIR);
+    let mut compile = AsyncCommand::new("cargo");
+    compile.current_dir(cairo_dir);
 
     let casm_path = Path::new(CASM_ROOT).join(&casm_remix_path);
 
@@ -276,7 +580,7 @@ pub async fn do_compile_to_casm(remix_file_path: PathBuf) -> Json<CompileRespons
         }
     }
 
-    let result = compile
+    let child = compile
         .arg("run")
         .arg("--release")
         .arg("--bin")
@@ -285,14 +589,35 @@ pub async fn do_compile_to_casm(remix_file_path: PathBuf) -> Json<CompileRespons
         .arg(&file_path)
         .arg(&casm_path)
         .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute starknet-sierra-compile");
+        .stdout(Stdio::piped())
+        .spawn()?;
 
     println!("LOG: ran command:{:?}", compile);
 
-    let output = result.wait_with_output().expect("Failed to wait on child");
+    let rewrites = [
+        (
+            file_path
+                .to_str()
+                .ok_or(CompileError::FilePathNotFound)?
+                .to_string(),
+            remix_file_path.clone(),
+        ),
+        (
+            casm_path
+                .to_str()
+                .ok_or(CompileError::FilePathNotFound)?
+                .to_string(),
+            casm_remix_path.clone(),
+        ),
+    ];
+    let (stdout, stderr, status) = run_streaming(child, &rewrites, sender).await?;
+    let message = stdout + &stderr;
+
+    if !matches!(status.code(), Some(0)) {
+        return Err(CompileError::CompilationFailed(message));
+    }
 
-    Json(CompileResponse {
+    Ok(Json(CompileResponse {
         file_content: match NamedFile::open(&casm_path).await.ok() {
             Some(file) => match file.path().to_str() {
                 Some(path) => match fs::read_to_string(path.to_string()).await {
@@ -303,111 +628,726 @@ pub async fn do_compile_to_casm(remix_file_path: PathBuf) -> Json<CompileRespons
             },
             None => "".to_string(),
         },
-        message: String::from_utf8(output.stderr)
-            .unwrap()
-            .replace(&file_path.to_str().unwrap().to_string(), &remix_file_path)
-            .replace(&casm_path.to_str().unwrap().to_string(), &casm_remix_path),
-        status: match output.status.code() {
+        message,
+        status: "Success".to_string(),
+    }))
+}
+
+/// Parse the summary emitted by `cairo-test` into structured per-test results
+///
+fn parse_cairo_test_output(output: &str) -> Vec<TestResult> {
+    let mut results = Vec::new();
+
+    for line in output.lines() {
+        let line = line.trim();
+        if !line.starts_with("test ") {
+            continue;
+        }
+
+        let Some(rest) = line.strip_prefix("test ") else {
+            continue;
+        };
+
+        let Some((name, outcome)) = rest.split_once("...") else {
+            continue;
+        };
+
+        let name = name.trim().to_string();
+        let outcome = outcome.trim();
+        let passed = outcome.starts_with("ok");
+
+        let gas_usage = outcome
+            .find("gas usage est.:")
+            .and_then(|idx| {
+                outcome[idx + "gas usage est.:".len()..]
+                    .trim()
+                    .split(')')
+                    .next()
+            })
+            .and_then(|gas| gas.trim().parse::<i64>().ok());
+
+        results.push(TestResult {
+            name,
+            passed,
+            gas_usage,
+        });
+    }
+
+    results
+}
+
+/// Compile and run the `#[test]`-annotated functions in a file or Scarb package
+///
+pub async fn do_run_tests(remix_file_path: PathBuf) -> Result<Json<TestRunResponse>, CompileError> {
+    let remix_file_path = remix_file_path
+        .to_str()
+        .ok_or(CompileError::FilePathNotFound)?
+        .to_string();
+
+    let file_path = get_file_path(&remix_file_path);
+
+    let mut test_runner = AsyncCommand::new("cargo");
+    test_runner.current_dir(CAIRO_DIR);
+
+    let child = test_runner
+        .arg("run")
+        .arg("--release")
+        .arg("--bin")
+        .arg("cairo-test")
+        .arg("--")
+        .arg(&file_path)
+        .stderr(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    println!("LOG: ran command:{:?}", test_runner);
+
+    let rewrites = [(
+        file_path
+            .to_str()
+            .ok_or(CompileError::FilePathNotFound)?
+            .to_string(),
+        remix_file_path.clone(),
+    )];
+    let (stdout, stderr, status) = run_streaming(child, &rewrites, None).await?;
+
+    let results = parse_cairo_test_output(&stdout);
+    let passed_count = results.iter().filter(|result| result.passed).count();
+    let failed_count = results.len() - passed_count;
+
+    Ok(Json(TestRunResponse {
+        status: match status.code() {
             Some(0) => "Success".to_string(),
-            Some(_) => "SierraCompilationFailed".to_string(),
+            Some(_) => "TestsFailed".to_string(),
             None => "UnknownError".to_string(),
         },
-    })
+        message: stdout + &stderr,
+        results,
+        passed_count,
+        failed_count,
+    }))
+}
+
+/// A user-defined Sierra function: its name and the statements that belong to it,
+/// each paired with its index in the program's statement block.
+struct SierraFunction {
+    name: String,
+    statements: Vec<(i64, String)>,
 }
 
-fn get_files_recursive(base_path: &Path) -> Vec<FileContentMap> {
+/// Split a Sierra program's text representation into its declared functions
+/// together with the statements that make up each one.
+///
+/// This expects the plain textual Sierra emitted by `cairo-compile` (see
+/// `compile_to_textual_sierra`), not the Starknet contract class JSON that
+/// `starknet-compile`/`scarb build` produce. That text does not number its statement
+/// lines: the type and libfunc declarations come first, then the unnumbered statement
+/// block, then the `name@idx(...) -> (...);` function declarations. A statement's index
+/// is therefore its position within that statement block, not any prefix on the line.
+fn parse_sierra_functions(sierra: &str) -> Vec<SierraFunction> {
+    let mut entries: Vec<(i64, String)> = Vec::new();
+    let mut statements: Vec<(i64, String)> = Vec::new();
+    let mut next_statement_idx: i64 = 0;
+
+    for line in sierra.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with("type ") || line.starts_with("libfunc ") {
+            continue;
+        }
+
+        if let Some(at_pos) = line.find('@') {
+            let name = line[..at_pos].trim().to_string();
+            let after_at = &line[at_pos + 1..];
+            if let Some(paren_pos) = after_at.find('(') {
+                if let Ok(idx) = after_at[..paren_pos].trim().parse::<i64>() {
+                    if !name.is_empty() {
+                        entries.push((idx, name));
+                        continue;
+                    }
+                }
+            }
+        }
+
+        statements.push((next_statement_idx, line.to_string()));
+        next_statement_idx += 1;
+    }
+
+    entries.sort_by_key(|(idx, _)| *idx);
+
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, (entry_idx, name))| {
+            let end_idx = entries.get(i + 1).map(|(idx, _)| *idx);
+            let statements = statements
+                .iter()
+                .filter(|(idx, _)| *idx >= *entry_idx && end_idx.map_or(true, |end| *idx < end))
+                .cloned()
+                .collect();
+            SierraFunction {
+                name: name.clone(),
+                statements,
+            }
+        })
+        .collect()
+}
+
+/// Build a caller -> callees call graph from `function_call<user@...>` invocations
+///
+fn build_call_graph(
+    functions: &[SierraFunction],
+) -> std::collections::HashMap<String, Vec<String>> {
+    let mut graph = std::collections::HashMap::new();
+
+    for function in functions {
+        let mut callees = Vec::new();
+        for (_, statement) in &function.statements {
+            if let Some(pos) = statement.find("function_call<user@") {
+                let rest = &statement[pos + "function_call<user@".len()..];
+                if let Some(end) = rest.find('>') {
+                    callees.push(rest[..end].to_string());
+                }
+            }
+        }
+        graph.insert(function.name.clone(), callees);
+    }
+
+    graph
+}
+
+/// Detect calls whose result is dropped without ever being used
+///
+fn detect_unused_return_value(functions: &[SierraFunction]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for function in functions {
+        for window in function.statements.windows(2) {
+            let [(idx, statement), (_, next_statement)] = window else {
+                continue;
+            };
+            if !statement.contains("function_call<user@") {
+                continue;
+            }
+            if next_statement.trim_start().starts_with("drop<") {
+                findings.push(Finding {
+                    detector_id: "unused-return-value".to_string(),
+                    severity: "Low".to_string(),
+                    message: "Return value is dropped without being used".to_string(),
+                    function: function.name.clone(),
+                    location: idx.to_string(),
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+/// Detect functions that are never reached from any other function in the program
+///
+fn detect_unused_functions(functions: &[SierraFunction]) -> Vec<Finding> {
+    let graph = build_call_graph(functions);
+    let mut called: std::collections::HashSet<&str> = std::collections::HashSet::new();
+    for callees in graph.values() {
+        for callee in callees {
+            called.insert(callee.as_str());
+        }
+    }
+
+    functions
+        .iter()
+        .filter(|function| {
+            !called.contains(function.name.as_str())
+                && !function.name.ends_with("::main")
+                && !function.name.ends_with("::constructor")
+        })
+        .map(|function| Finding {
+            detector_id: "unused-function".to_string(),
+            severity: "Informational".to_string(),
+            message: "Function is never called from within the program".to_string(),
+            function: function.name.clone(),
+            location: function
+                .statements
+                .first()
+                .map(|(idx, _)| idx.to_string())
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Detect low-level `call_contract`/`library_call` invocations whose result is not pattern-matched
+///
+fn detect_unchecked_low_level_call(functions: &[SierraFunction]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for function in functions {
+        for window in function.statements.windows(2) {
+            let [(idx, statement), (_, next_statement)] = window else {
+                continue;
+            };
+            let is_low_level_call = statement.contains("call_contract_syscall")
+                || statement.contains("library_call_syscall");
+            if is_low_level_call && !next_statement.trim_start().starts_with("enum_match") {
+                findings.push(Finding {
+                    detector_id: "unchecked-low-level-call".to_string(),
+                    severity: "Medium".to_string(),
+                    message: "Result of a low-level call is not checked".to_string(),
+                    function: function.name.clone(),
+                    location: idx.to_string(),
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+/// Render the call graph as a simple "caller -> callee" text listing
+///
+fn print_call_graph(functions: &[SierraFunction]) -> String {
+    let graph = build_call_graph(functions);
+    let mut lines = Vec::new();
+    for function in functions {
+        for callee in graph.get(&function.name).into_iter().flatten() {
+            lines.push(format!("{} -> {}", function.name, callee));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Render each function's statements as a simple (unbranched) control flow listing
+///
+fn print_cfg(functions: &[SierraFunction]) -> String {
+    let mut lines = Vec::new();
+    for function in functions {
+        lines.push(format!("function {}:", function.name));
+        for (idx, statement) in &function.statements {
+            lines.push(format!("  {}: {}", idx, statement));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Compile a single `.cairo` file to genuine textual Sierra for the detector subsystem.
+///
+/// `do_compile_to_sierra` shells out to `starknet-compile`, which emits a Starknet
+/// contract class as JSON (`{"sierra_program": [...], "sierra_program_debug_info": ...}`),
+/// not the `type `/`libfunc `/statement-listing text `parse_sierra_functions` parses. This
+/// instead runs the plain `cairo-compile` binary, which emits that textual format directly.
+async fn compile_to_textual_sierra(
+    remix_file_path: &str,
+    cairo_dir: &str,
+) -> Result<String, CompileError> {
+    let file_path = get_file_path(remix_file_path);
+    let sierra_remix_path = remix_file_path.replace(&get_file_ext(remix_file_path), "sierra");
+    let sierra_path = Path::new(SIERRA_ROOT).join(&sierra_remix_path);
+
+    if let Some(parent) = sierra_path.parent() {
+        fs::create_dir_all(parent).await.ok();
+    }
+
+    let mut compile = AsyncCommand::new("cargo");
+    compile.current_dir(cairo_dir);
+
+    let child = compile
+        .arg("run")
+        .arg("--release")
+        .arg("--bin")
+        .arg("cairo-compile")
+        .arg("--")
+        .arg(&file_path)
+        .arg(&sierra_path)
+        .stderr(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    println!("LOG: ran command:{:?}", compile);
+
+    let rewrites = [(
+        file_path
+            .to_str()
+            .ok_or(CompileError::FilePathNotFound)?
+            .to_string(),
+        remix_file_path.to_string(),
+    )];
+    let (_stdout, message, status) = run_streaming(child, &rewrites, None).await?;
+
+    if !matches!(status.code(), Some(0)) {
+        return Err(CompileError::CompilationFailed(message));
+    }
+
+    Ok(fs::read_to_string(&sierra_path).await?)
+}
+
+/// Run a static-analysis pass over the Sierra output of a compiled file, with every
+/// detector pass enabled and no printers.
+pub async fn do_analyze(remix_file_path: PathBuf) -> Result<Json<AnalyzeResponse>, CompileError> {
+    do_analyze_with_options(remix_file_path, vec![], vec![]).await
+}
+
+/// Same as [`do_analyze`], with a choice of passes.
+///
+/// `detectors` selects which detector passes run (defaults to all of them when empty);
+/// `printers` may additionally request `"call-graph"` and/or `"cfg"` text dumps.
+pub async fn do_analyze_with_options(
+    remix_file_path: PathBuf,
+    detectors: Vec<String>,
+    printers: Vec<String>,
+) -> Result<Json<AnalyzeResponse>, CompileError> {
+    let remix_file_path_str = remix_file_path
+        .to_str()
+        .ok_or(CompileError::FilePathNotFound)?
+        .to_string();
+
+    // A single `.cairo` file can be compiled straight to genuine textual Sierra with
+    // `cairo-compile`. Scarb projects only ever produce Starknet contract class JSON
+    // (`*.contract_class.json`), which would need a full Sierra bytecode decoder to turn
+    // back into the statement listing the detectors expect; until that exists, be honest
+    // about the gap rather than silently returning zero findings.
+    let sierra_sources = if get_file_ext(&remix_file_path_str) == "cairo" {
+        let cairo_dir = resolve_cairo_dir(None)?;
+        vec![compile_to_textual_sierra(&remix_file_path_str, &cairo_dir).await?]
+    } else {
+        return Err(CompileError::UnsupportedAnalysisTarget(
+            "Sierra analysis is only supported for single .cairo files today; Scarb \
+             projects only produce Starknet contract class JSON, which this analyzer \
+             cannot yet decode back into textual Sierra"
+                .to_string(),
+        ));
+    };
+
+    let run_all = detectors.is_empty();
+    let mut message_parts = Vec::new();
+    let mut findings = Vec::new();
+
+    for sierra in &sierra_sources {
+        let functions = parse_sierra_functions(sierra);
+
+        if printers.iter().any(|p| p == "call-graph") {
+            message_parts.push(print_call_graph(&functions));
+        }
+        if printers.iter().any(|p| p == "cfg") {
+            message_parts.push(print_cfg(&functions));
+        }
+
+        if run_all || detectors.iter().any(|d| d == "unused-return-value") {
+            findings.extend(detect_unused_return_value(&functions));
+        }
+        if run_all || detectors.iter().any(|d| d == "unused-function") {
+            findings.extend(detect_unused_functions(&functions));
+        }
+        if run_all || detectors.iter().any(|d| d == "unchecked-low-level-call") {
+            findings.extend(detect_unchecked_low_level_call(&functions));
+        }
+    }
+
+    Ok(Json(AnalyzeResponse {
+        status: "Success".to_string(),
+        message: message_parts.join("\n\n"),
+        findings,
+    }))
+}
+
+fn get_files_recursive(base_path: &Path) -> Result<Vec<FileContentMap>, CompileError> {
     let mut file_content_map_array: Vec<FileContentMap> = Vec::new();
 
     if base_path.is_dir() {
-        for entry in base_path.read_dir().unwrap().flatten() {
+        for entry in base_path.read_dir()?.flatten() {
             let path = entry.path();
             if path.is_dir() {
-                file_content_map_array.extend(get_files_recursive(&path));
+                file_content_map_array.extend(get_files_recursive(&path)?);
             } else if let Ok(content) = std::fs::read_to_string(&path) {
-                let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+                let file_name = path
+                    .file_name()
+                    .ok_or(CompileError::FilePathNotFound)?
+                    .to_string_lossy()
+                    .to_string();
                 let file_content = content;
                 let file_content_map = FileContentMap {
                     file_name,
                     file_content,
+                    artifact_kind: None,
+                    package_name: None,
+                    contract_name: None,
                 };
                 file_content_map_array.push(file_content_map);
             }
         }
     }
 
-    file_content_map_array
+    Ok(file_content_map_array)
+}
+
+/// A single contract entry from a `*.starknet_artifacts.json` file emitted by `scarb build`
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct StarknetContractArtifact {
+    package_name: String,
+    contract_name: String,
+    artifacts: std::collections::HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct StarknetArtifacts {
+    contracts: Vec<StarknetContractArtifact>,
+}
+
+/// Map every artifact file name produced by `scarb build` to the package/contract/kind
+/// it belongs to, by reading the `*.starknet_artifacts.json` files Scarb writes alongside them.
+fn read_starknet_artifacts(
+    target_dir: &Path,
+) -> std::collections::HashMap<String, (String, String, String)> {
+    let mut file_info = std::collections::HashMap::new();
+
+    let Ok(entries) = target_dir.read_dir() else {
+        return file_info;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json")
+            || !path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(".starknet_artifacts.json"))
+        {
+            continue;
+        }
+
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let Ok(artifacts) =
+            rocket::serde::json::serde_json::from_str::<StarknetArtifacts>(&content)
+        else {
+            continue;
+        };
+
+        for contract in artifacts.contracts {
+            for (kind, file_name) in contract.artifacts {
+                file_info.insert(
+                    file_name,
+                    (
+                        contract.package_name.clone(),
+                        contract.contract_name.clone(),
+                        kind,
+                    ),
+                );
+            }
+        }
+    }
+
+    file_info
 }
 
 /// Run Scarb to compile a project
 ///
+/// `contract` optionally restricts the returned artifacts to a single `package::Contract`,
+/// and `features` is passed through to `scarb build --features` to select which Cargo-style
+/// features are enabled for the build.
 pub async fn do_scarb_compile(
     remix_file_path: PathBuf,
-) -> Result<Json<ScarbCompileResponse>, String> {
-    let remix_file_path = match remix_file_path.to_str() {
-        Some(path) => path.to_string(),
-        None => {
-            return Ok(Json(ScarbCompileResponse {
-                file_content_map_array: vec![],
-                message: "File path not found".to_string(),
-                status: "FileNotFound".to_string(),
-            }));
-        }
-    };
+    contract: Option<String>,
+    features: Vec<String>,
+) -> Result<Json<ScarbCompileResponse>, CompileError> {
+    do_scarb_compile_inner(remix_file_path, contract, features, None).await
+}
+
+/// Same as [`do_scarb_compile`], but forwards each output line through `sender` as Scarb
+/// produces it instead of waiting for the whole build to finish.
+pub async fn do_scarb_compile_streamed(
+    remix_file_path: PathBuf,
+    contract: Option<String>,
+    features: Vec<String>,
+    sender: Sender<CompileStreamLine>,
+) -> Result<Json<ScarbCompileResponse>, CompileError> {
+    do_scarb_compile_inner(remix_file_path, contract, features, Some(&sender)).await
+}
+
+async fn do_scarb_compile_inner(
+    remix_file_path: PathBuf,
+    contract: Option<String>,
+    features: Vec<String>,
+    sender: Option<&Sender<CompileStreamLine>>,
+) -> Result<Json<ScarbCompileResponse>, CompileError> {
+    let remix_file_path = remix_file_path
+        .to_str()
+        .ok_or(CompileError::FilePathNotFound)?
+        .to_string();
 
     let file_path = get_file_path(&remix_file_path);
 
-    let mut compile = Command::new("scarb");
+    let mut compile = AsyncCommand::new("scarb");
     compile.current_dir(&file_path);
+    compile.arg("build");
+
+    if !features.is_empty() {
+        compile.arg("--features").arg(features.join(","));
+    }
 
-    let result = compile
-        .arg("build")
+    let child = compile
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute scarb build");
+        .spawn()?;
 
     println!("LOG: ran command:{:?}", compile);
 
-    let output = result.wait_with_output().expect("Failed to wait on child");
+    let rewrites = [(
+        file_path
+            .to_str()
+            .ok_or(CompileError::FilePathNotFound)?
+            .to_string(),
+        remix_file_path.clone(),
+    )];
+    let (stdout, stderr, status) = run_streaming(child, &rewrites, sender).await?;
+    let message = stdout + &stderr;
+
+    if !matches!(status.code(), Some(0)) {
+        return Err(CompileError::CompilationFailed(message));
+    }
+
+    let target_dir = file_path.join("target/dev");
+    let artifact_info = read_starknet_artifacts(&target_dir);
+
+    let mut file_content_map_array = get_files_recursive(&target_dir)?;
+    for file_content_map in &mut file_content_map_array {
+        if let Some((package_name, contract_name, kind)) =
+            artifact_info.get(&file_content_map.file_name)
+        {
+            file_content_map.package_name = Some(package_name.clone());
+            file_content_map.contract_name = Some(contract_name.clone());
+            file_content_map.artifact_kind = Some(kind.clone());
+        }
+    }
+
+    if let Some(contract) = contract {
+        if artifact_info.is_empty() {
+            return Err(CompileError::ArtifactsMetadataMissing);
+        }
+
+        file_content_map_array.retain(|file_content_map| {
+            file_content_map.contract_name.as_deref() == Some(contract.as_str())
+                || file_content_map
+                    .package_name
+                    .as_ref()
+                    .zip(file_content_map.contract_name.as_ref())
+                    .map(|(package, name)| format!("{package}::{name}"))
+                    .as_deref()
+                    == Some(contract.as_str())
+        });
+
+        if file_content_map_array.is_empty() {
+            return Err(CompileError::ContractNotFound(contract));
+        }
+    }
 
     Ok(Json(ScarbCompileResponse {
-        file_content_map_array: get_files_recursive(&file_path.join("target/dev")),
-        message: String::from_utf8(output.stdout)
-            .unwrap()
-            .replace(&file_path.to_str().unwrap().to_string(), &remix_file_path)
-            + &String::from_utf8(output.stderr)
-                .unwrap()
-                .replace(&file_path.to_str().unwrap().to_string(), &remix_file_path),
-        status: match output.status.code() {
-            Some(0) => "Success".to_string(),
-            Some(_) => "SierraCompilationFailed".to_string(),
-            None => "UnknownError".to_string(),
-        },
+        file_content_map_array,
+        message,
+        status: "Success".to_string(),
     }))
 }
 
 /// Run Cairo --version to return Cairo version string
 ///
-pub fn do_cairo_version() -> Result<String, String> {
+pub fn do_cairo_version(version: Option<String>) -> Result<String, CompileError> {
+    let cairo_dir = resolve_cairo_dir(version.as_deref())?;
+
     let mut version_caller = Command::new("cargo");
-    version_caller.current_dir(CAIRO_DIR);
-    match String::from_utf8(
-        version_caller
-            .arg("run")
-            .arg("-q")
-            .arg("--release")
-            .arg("--bin")
-            .arg("cairo-compile")
-            .arg("--")
-            .arg("--version")
-            .stdout(Stdio::piped())
-            .spawn()
-            .expect("Failed to execute cairo-compile")
-            .wait_with_output()
-            .expect("Failed to wait on child")
-            .stdout,
-    ) {
-        Ok(version) => Ok(version),
-        Err(e) => Err(e.to_string()),
-    }
-}
\ No newline at end of file
+    version_caller.current_dir(cairo_dir);
+
+    let output = version_caller
+        .arg("run")
+        .arg("-q")
+        .arg("--release")
+        .arg("--bin")
+        .arg("cairo-compile")
+        .arg("--")
+        .arg("--version")
+        .stdout(Stdio::piped())
+        .spawn()?
+        .wait_with_output()?;
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal but representative slice of the textual Sierra format `cairo-compile`
+    /// emits (what `compile_to_textual_sierra` feeds the detector subsystem): type/libfunc
+    /// declarations, an unnumbered statement block per function, and trailing
+    /// `name@idx(...) -> (...);` function declarations. There is no `cairo-compile`
+    /// binary available in this sandbox to run for a true end-to-end subprocess test, so
+    /// this fixture is what exercises `parse_sierra_functions` and the detectors against
+    /// that real output shape.
+    const SIERRA_FIXTURE: &str = r#"
+type felt252 = felt252;
+type RangeCheck = RangeCheck [storable: true, drop: false, dup: false, zero_sized: false];
+
+libfunc felt252_const<0> = felt252_const<0>;
+libfunc store_temp<felt252> = store_temp<felt252>;
+libfunc function_call<user@test::test::helper> = function_call<user@test::test::helper>;
+libfunc call_contract_syscall = call_contract_syscall;
+libfunc drop<felt252> = drop<felt252>;
+libfunc return = return;
+
+felt252_const<0>() -> ([0]);
+store_temp<felt252>([0]) -> ([0]);
+function_call<user@test::test::helper>([0]) -> ([1]);
+drop<felt252>([1]) -> ();
+call_contract_syscall([2]) -> ([3]);
+store_temp<felt252>([3]) -> ([3]);
+return([3]);
+
+felt252_const<0>() -> ([0]);
+store_temp<felt252>([0]) -> ([0]);
+return([0]);
+
+test::test::main@0([0]: RangeCheck) -> (RangeCheck);
+test::test::helper@7([0]: felt252) -> (felt252);
+"#;
+
+    #[test]
+    fn parse_sierra_functions_numbers_statements_by_position() {
+        let functions = parse_sierra_functions(SIERRA_FIXTURE);
+        assert_eq!(functions.len(), 2);
+
+        let main = functions
+            .iter()
+            .find(|function| function.name == "test::test::main")
+            .expect("main function parsed");
+        assert_eq!(main.statements.len(), 7);
+        assert_eq!(main.statements.first().map(|(idx, _)| *idx), Some(0));
+
+        let helper = functions
+            .iter()
+            .find(|function| function.name == "test::test::helper")
+            .expect("helper function parsed");
+        assert_eq!(helper.statements.len(), 3);
+        assert_eq!(helper.statements.first().map(|(idx, _)| *idx), Some(7));
+    }
+
+    #[test]
+    fn detectors_fire_once_statements_are_populated() {
+        let functions = parse_sierra_functions(SIERRA_FIXTURE);
+
+        let unused_return = detect_unused_return_value(&functions);
+        assert!(unused_return
+            .iter()
+            .any(|finding| finding.function == "test::test::main"));
+
+        let unchecked_call = detect_unchecked_low_level_call(&functions);
+        assert!(unchecked_call
+            .iter()
+            .any(|finding| finding.function == "test::test::main"));
+
+        // helper is reached via `function_call<user@test::test::helper>` from main, so it
+        // must not be reported as an unused function.
+        let unused_functions = detect_unused_functions(&functions);
+        assert!(unused_functions.is_empty());
+    }
+}